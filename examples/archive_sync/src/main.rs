@@ -1,10 +1,13 @@
-use postgresql_archive::blocking::{extract, get_archive};
-use postgresql_archive::{Result, LATEST};
+use postgresql_archive::blocking::{extract_with_settings, get_archive};
+use postgresql_archive::{Result, Settings, LATEST};
 
 fn main() -> Result<()> {
-    let (archive_version, archive, _hash) = get_archive(&LATEST)?;
+    let (archive_version, archive, hash) = get_archive(&LATEST)?;
     let out_dir = tempfile::tempdir()?.into_path();
-    extract(&archive, &out_dir)?;
+    // Route extraction through a cache directory shared across runs/versions, so re-installing
+    // the same PostgreSQL build only ever re-stores the chunks that actually changed.
+    let settings = Settings::new().cache_dir(std::env::temp_dir().join("postgresql_archive_cache"));
+    extract_with_settings(&archive, &hash, &out_dir, &settings)?;
     println!(
         "PostgreSQL {} extracted to {}",
         archive_version,
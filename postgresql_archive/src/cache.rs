@@ -0,0 +1,199 @@
+use crate::chunker;
+use crate::encryption::EncryptionKey;
+use crate::error::Result;
+use crate::settings::Settings;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Content-addressed id of a single cached chunk: the hex-encoded SHA-256 of its bytes.
+pub type ChunkId = String;
+
+/// The ordered list of chunk ids that reconstruct one extracted file, plus its total length.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Manifest {
+    pub chunk_ids: Vec<ChunkId>,
+    pub len: u64,
+}
+
+/// Running totals for a [`ChunkCache`], so callers can see how much de-duplication is saving.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Total bytes across every file stored, counting duplicates.
+    pub total_bytes: u64,
+    /// Bytes actually written to the cache directory (unique chunks only).
+    pub stored_bytes: u64,
+}
+
+impl CacheStats {
+    /// Bytes saved by de-duplication: `total_bytes - stored_bytes`.
+    #[must_use]
+    pub fn deduplicated_bytes(&self) -> u64 {
+        self.total_bytes.saturating_sub(self.stored_bytes)
+    }
+}
+
+/// A deduplicating, content-addressed store for the chunks produced by [`crate::chunker`].
+///
+/// Each unique chunk is written once under the cache directory, keyed by the SHA-256 of its
+/// plaintext bytes; files are represented as a [`Manifest`] of chunk ids and reassembled on
+/// read. When an [`EncryptionKey`] is set, chunks are sealed before being written and opened
+/// transparently on read, so the cache directory is unreadable without the key.
+#[derive(Clone)]
+pub struct ChunkCache {
+    dir: PathBuf,
+    encryption_key: Option<EncryptionKey>,
+}
+
+impl ChunkCache {
+    /// Open (creating if necessary) a chunk cache rooted at `dir`.
+    ///
+    /// # Errors
+    /// Returns an error if `dir` cannot be created.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            encryption_key: None,
+        })
+    }
+
+    /// Seal every chunk written to this cache with `key`, and transparently open it on read.
+    #[must_use]
+    pub fn with_encryption_key(mut self, key: EncryptionKey) -> Self {
+        self.encryption_key = Some(key);
+        self
+    }
+
+    /// Open (creating if necessary) a chunk cache rooted at `dir`, applying the at-rest
+    /// encryption key configured on `settings`, if any. [`crate::extractor::extract_with_settings`]
+    /// opens its cache this way, which makes encryption an opt-in [`Settings`] knob rather than
+    /// something callers wire up by hand; plain `extract` uses no cache at all.
+    ///
+    /// # Errors
+    /// Returns an error if `dir` cannot be created, or `settings`'s key can't be resolved (e.g.
+    /// both an inline key and a key file were set, or the key file is unreadable).
+    pub fn open_with_settings(dir: impl Into<PathBuf>, settings: &Settings) -> Result<Self> {
+        let cache = Self::open(dir)?;
+        Ok(match settings.resolve_encryption_key()? {
+            Some(key) => cache.with_encryption_key(key),
+            None => cache,
+        })
+    }
+
+    fn chunk_path(&self, id: &ChunkId) -> PathBuf {
+        self.dir.join(id)
+    }
+
+    /// Split `data` into content-defined chunks, writing any not already present in the cache,
+    /// and return the resulting manifest. `stats` is updated with the bytes seen and the bytes
+    /// actually written.
+    ///
+    /// # Errors
+    /// Returns an error if a chunk cannot be sealed or written to the cache directory.
+    pub fn store(&self, data: &[u8], stats: &mut CacheStats) -> Result<Manifest> {
+        let mut manifest = Manifest {
+            chunk_ids: Vec::new(),
+            len: data.len() as u64,
+        };
+        stats.total_bytes += data.len() as u64;
+
+        for chunk in chunker::chunks(data) {
+            // Keyed by the plaintext digest so identical chunks still dedupe even though
+            // sealing produces different ciphertext each time (fresh nonce per blob).
+            let id = hex::encode(Sha256::digest(chunk));
+            let path = self.chunk_path(&id);
+            if !path.exists() {
+                let bytes = match &self.encryption_key {
+                    Some(key) => key.seal(chunk)?,
+                    None => chunk.to_vec(),
+                };
+                stats.stored_bytes += bytes.len() as u64;
+                fs::write(&path, bytes)?;
+            }
+            manifest.chunk_ids.push(id);
+        }
+
+        Ok(manifest)
+    }
+
+    /// Reassemble a file's bytes from its manifest, fetching only the chunks it references.
+    ///
+    /// # Errors
+    /// Returns an error if any referenced chunk is missing from the cache, or
+    /// [`Error::AuthenticationFailed`](crate::Error::AuthenticationFailed) if a chunk fails to
+    /// open with the configured encryption key.
+    pub fn reassemble(&self, manifest: &Manifest) -> Result<Vec<u8>> {
+        let mut data = Vec::with_capacity(manifest.len as usize);
+        for id in &manifest.chunk_ids {
+            let bytes = fs::read(self.chunk_path(id))?;
+            let chunk = match &self.encryption_key {
+                Some(key) => key.open(&bytes)?,
+                None => bytes,
+            };
+            data.extend(chunk);
+        }
+        Ok(data)
+    }
+
+    /// The directory backing this cache.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn test_store_deduplicates_repeated_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ChunkCache::open(dir.path()).unwrap();
+        let data: Vec<u8> = (0..500_000).map(|i| (i % 251) as u8).collect();
+
+        let mut stats = CacheStats::default();
+        let first = cache.store(&data, &mut stats).unwrap();
+        let first_stored = stats.stored_bytes;
+        assert!(first_stored > 0);
+
+        let second = cache.store(&data, &mut stats).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(stats.stored_bytes, first_stored, "no new chunks written");
+        assert_eq!(stats.total_bytes, (data.len() as u64) * 2);
+    }
+
+    #[test]
+    fn test_reassemble_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ChunkCache::open(dir.path()).unwrap();
+        let data = b"some file content to chunk and reassemble".to_vec();
+
+        let mut stats = CacheStats::default();
+        let manifest = cache.store(&data, &mut stats).unwrap();
+        let reassembled = cache.reassemble(&manifest).unwrap();
+
+        assert_eq!(data, reassembled);
+    }
+
+    #[test]
+    fn test_encrypted_cache_round_trips_and_seals_on_disk() {
+        use crate::encryption::EncryptionKey;
+
+        let dir = tempfile::tempdir().unwrap();
+        let key = EncryptionKey::from_bytes([3_u8; 32]);
+        let cache = ChunkCache::open(dir.path()).unwrap().with_encryption_key(key);
+        let data = b"credentials and other sensitive bytes".to_vec();
+
+        let mut stats = CacheStats::default();
+        let manifest = cache.store(&data, &mut stats).unwrap();
+        assert_eq!(cache.reassemble(&manifest).unwrap(), data);
+
+        let chunk_path = cache.chunk_path(&manifest.chunk_ids[0]);
+        let on_disk = fs::read(chunk_path).unwrap();
+        assert_ne!(on_disk, data, "chunk must not be stored as plaintext");
+    }
+}
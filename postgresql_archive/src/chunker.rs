@@ -0,0 +1,131 @@
+//! Content-defined chunking (CDC) using a Gear rolling hash, so that two files differing by a
+//! small insertion or deletion still share most of their chunks.
+
+/// Chunks smaller than this are never split further, to bound the number of chunks produced by
+/// pathological (e.g. all-zero) input.
+const MIN_CHUNK_SIZE: usize = 2 * 1024;
+
+/// Chunks are forced to end at this size even if no boundary hash has matched yet.
+const MAX_CHUNK_SIZE: usize = 256 * 1024;
+
+/// A boundary is emitted when `hash & BOUNDARY_MASK == 0`; the mask's bit count sets the
+/// (probabilistic) average chunk size, here `2^16 == 64 KiB`.
+const BOUNDARY_MASK: u64 = (1 << 16) - 1;
+
+const GEAR_TABLE_SIZE: usize = 256;
+
+/// A table of pseudo-random 64-bit constants, one per input byte value, used to mix each byte
+/// into the rolling hash. Generated deterministically with `SplitMix64` so the table (and thus
+/// chunk boundaries) are stable across builds.
+const fn gear_table() -> [u64; GEAR_TABLE_SIZE] {
+    let mut table = [0_u64; GEAR_TABLE_SIZE];
+    let mut seed: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut i = 0;
+    while i < GEAR_TABLE_SIZE {
+        seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = seed;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        table[i] = z;
+        i += 1;
+    }
+    table
+}
+
+static GEAR: [u64; GEAR_TABLE_SIZE] = gear_table();
+
+/// Compute the byte offsets (relative to the start of `data`) where each chunk ends.
+#[must_use]
+fn chunk_boundaries(data: &[u8]) -> Vec<usize> {
+    let mut boundaries = Vec::new();
+    let mut chunk_start = 0;
+    let mut hash: u64 = 0;
+
+    for (i, &byte) in data.iter().enumerate() {
+        hash = (hash << 1).wrapping_add(GEAR[byte as usize]);
+        let chunk_len = i + 1 - chunk_start;
+        let at_boundary = hash & BOUNDARY_MASK == 0;
+        if chunk_len >= MIN_CHUNK_SIZE && (at_boundary || chunk_len >= MAX_CHUNK_SIZE) {
+            boundaries.push(i + 1);
+            chunk_start = i + 1;
+            hash = 0;
+        }
+    }
+
+    if chunk_start < data.len() {
+        boundaries.push(data.len());
+    }
+
+    boundaries
+}
+
+/// Split `data` into content-defined chunks.
+///
+/// Chunk boundaries are determined by a rolling hash over the content itself (not fixed offsets),
+/// so inserting or removing bytes only perturbs the chunks adjacent to the edit.
+#[must_use]
+pub fn chunks(data: &[u8]) -> Vec<&[u8]> {
+    let mut start = 0;
+    chunk_boundaries(data)
+        .into_iter()
+        .map(|end| {
+            let chunk = &data[start..end];
+            start = end;
+            chunk
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random bytes (xorshift64), so fixtures don't carry a short repeating
+    /// period that could mask or fake a chunk boundary property under test.
+    fn pseudo_random_bytes(len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed | 1;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state % 256) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_chunks_reassemble_to_input() {
+        let data = pseudo_random_bytes(500_000, 1);
+        let chunks = chunks(&data);
+        let reassembled: Vec<u8> = chunks.iter().flat_map(|chunk| chunk.to_vec()).collect();
+        assert_eq!(data, reassembled);
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.len() <= MAX_CHUNK_SIZE);
+        }
+    }
+
+    #[test]
+    fn test_small_input_is_single_chunk() {
+        let data = b"too small to split";
+        assert_eq!(chunks(data), vec![data.as_slice()]);
+    }
+
+    #[test]
+    fn test_insertion_only_perturbs_nearby_chunks() {
+        let base = pseudo_random_bytes(500_000, 1);
+        let mut edited = base.clone();
+        edited.splice(250_000..250_000, pseudo_random_bytes(37, 2));
+
+        let base_chunks: std::collections::HashSet<&[u8]> = chunks(&base).into_iter().collect();
+        let edited_chunks = chunks(&edited);
+        let shared = edited_chunks
+            .iter()
+            .filter(|chunk| base_chunks.contains(*chunk))
+            .count();
+
+        assert!(shared > edited_chunks.len() / 2);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::error::{Error, Result};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use std::fs;
+use std::path::Path;
+
+/// Length in bytes of the random nonce prefixed to every sealed blob.
+const NONCE_LEN: usize = 12;
+
+/// A 256-bit key used to seal cached archive chunks at rest. Files extracted into an output
+/// directory are written in plaintext; only the chunks in the cache directory are sealed.
+///
+/// Accepted either as raw bytes or from a key file, so key material never needs to live in
+/// process arguments.
+#[derive(Clone)]
+pub struct EncryptionKey([u8; 32]);
+
+impl EncryptionKey {
+    /// Use `key` directly as key material.
+    #[must_use]
+    pub fn from_bytes(key: [u8; 32]) -> Self {
+        Self(key)
+    }
+
+    /// Read hex-encoded key material from `path`, trimming a trailing newline.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if `path` can't be read, or [`Error::InvalidKeyMaterial`] if its
+    /// contents don't hex-decode to exactly 32 bytes.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let trimmed = contents.trim_end_matches(['\n', '\r']);
+        let bytes = hex::decode(trimmed).map_err(|_| Error::InvalidKeyMaterial)?;
+        let key: [u8; 32] = bytes.try_into().map_err(|_| Error::InvalidKeyMaterial)?;
+        Ok(Self(key))
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new((&self.0).into())
+    }
+
+    /// Seal `plaintext`, prefixing a fresh random nonce to the returned ciphertext and
+    /// authenticating the content.
+    ///
+    /// # Errors
+    /// Returns [`Error::Encryption`] if sealing fails.
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let mut ciphertext = self
+            .cipher()
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| Error::Encryption)?;
+        let mut sealed = nonce.to_vec();
+        sealed.append(&mut ciphertext);
+        Ok(sealed)
+    }
+
+    /// Open a blob previously produced by [`seal`](Self::seal).
+    ///
+    /// # Errors
+    /// Returns [`Error::AuthenticationFailed`] if `sealed` is truncated, corrupted, or was sealed
+    /// with a different key, distinguishing that case from ordinary I/O corruption.
+    pub fn open(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+        if sealed.len() < NONCE_LEN {
+            return Err(Error::AuthenticationFailed);
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        self.cipher()
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| Error::AuthenticationFailed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_open_round_trip() {
+        let key = EncryptionKey::from_bytes([7_u8; 32]);
+        let sealed = key.seal(b"super secret chunk").unwrap();
+        assert_eq!(key.open(&sealed).unwrap(), b"super secret chunk");
+    }
+
+    #[test]
+    fn test_open_with_wrong_key_fails() {
+        let key = EncryptionKey::from_bytes([7_u8; 32]);
+        let other = EncryptionKey::from_bytes([9_u8; 32]);
+        let sealed = key.seal(b"super secret chunk").unwrap();
+        assert!(matches!(
+            other.open(&sealed),
+            Err(Error::AuthenticationFailed)
+        ));
+    }
+
+    #[test]
+    fn test_open_truncated_fails() {
+        let key = EncryptionKey::from_bytes([7_u8; 32]);
+        assert!(matches!(key.open(b"short"), Err(Error::AuthenticationFailed)));
+    }
+}
@@ -0,0 +1,33 @@
+use thiserror::Error;
+
+/// Errors returned by this crate.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// An I/O error occurred.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// The downloaded archive's digest didn't match the hash published for it.
+    #[error("hash mismatch: expected {expected}, computed {actual}")]
+    HashMismatch { expected: String, actual: String },
+
+    /// The expected hash's length didn't match a supported digest algorithm.
+    #[error("unsupported hash length: {0} hex characters")]
+    UnsupportedHashLength(usize),
+
+    /// Key material supplied for at-rest encryption wasn't valid (wrong length or encoding), or
+    /// was supplied both inline and as a key file.
+    #[error("invalid encryption key material")]
+    InvalidKeyMaterial,
+
+    /// Sealing a blob for at-rest encryption failed.
+    #[error("encryption failed")]
+    Encryption,
+
+    /// Opening a sealed blob failed: it was corrupted, truncated, or sealed with a different key.
+    #[error("failed to authenticate encrypted content; wrong key or corrupted data")]
+    AuthenticationFailed,
+}
+
+/// Result type for this crate's functions that return an [`Error`].
+pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -0,0 +1,284 @@
+use crate::cache::{CacheStats, ChunkCache};
+use crate::error::Result;
+use crate::hasher::{self, HashAlgorithm};
+use crate::settings::Settings;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+/// Extract a downloaded `PostgreSQL` archive into `out_dir`, verifying it against `hash` first
+/// (selecting the digest algorithm from the hash's length).
+///
+/// This never caches or encrypts: the archive is always unpacked directly into `out_dir`. Use
+/// [`extract_with_settings`] instead to opt into the deduplicating [`ChunkCache`] and its at-rest
+/// encryption via a [`Settings`].
+///
+/// # Errors
+/// Returns [`Error::HashMismatch`](crate::Error::HashMismatch) if `archive` doesn't match `hash`,
+/// or an I/O error if it can't be read or unpacked.
+pub fn extract(archive: &Path, hash: &str, out_dir: &Path) -> Result<()> {
+    extract_verified(archive, hash, None, out_dir, None)?;
+    Ok(())
+}
+
+/// Extract a downloaded `PostgreSQL` archive into `out_dir`, verifying it against `hash`, the
+/// `Settings`-driven counterpart to [`extract`].
+///
+/// When `settings` has a [`cache_dir`](Settings::cache_dir), extraction is routed through a
+/// [`ChunkCache`] rooted there (opened with [`ChunkCache::open_with_settings`], which also applies
+/// `settings`'s encryption key, if any) instead of unpacking the archive directly, and the
+/// returned [`CacheStats`] describe the bytes extracted versus the bytes actually stored. With no
+/// `cache_dir`, this behaves exactly like [`extract`] and returns `None`.
+///
+/// # Errors
+/// Returns [`Error::HashMismatch`](crate::Error::HashMismatch) if `archive` doesn't match `hash`,
+/// an I/O error if it can't be read, unpacked, or (with a cache) written into it, or an error
+/// resolving `settings`'s encryption key (see [`Settings::resolve_encryption_key`]).
+pub fn extract_with_settings(
+    archive: &Path,
+    hash: &str,
+    out_dir: &Path,
+    settings: &Settings,
+) -> Result<Option<CacheStats>> {
+    let cache = settings
+        .get_cache_dir()
+        .map(|dir| ChunkCache::open_with_settings(dir, settings))
+        .transpose()?;
+    extract_verified(archive, hash, None, out_dir, cache.as_ref())
+}
+
+/// Extract a downloaded `PostgreSQL` archive into `out_dir`, verifying it against `hash` using
+/// the given digest `algorithm` (or auto-detecting one from the hash's length when `algorithm`
+/// is `None`).
+///
+/// When `cache` is given, extracted files are routed through that deduplicating, content-
+/// addressed [`ChunkCache`] instead of being unpacked directly: each file is split into chunks
+/// and stored there, chunks already present (e.g. shared with a previously installed version)
+/// aren't rewritten, and the returned [`CacheStats`] describe the bytes extracted versus the
+/// bytes actually stored. With no `cache`, the archive is unpacked directly and `None` is
+/// returned.
+///
+/// The digest is computed incrementally while reading, so large archives never need to be
+/// buffered fully in memory.
+///
+/// # Errors
+/// Returns [`Error::HashMismatch`](crate::Error::HashMismatch) if `archive` doesn't match `hash`,
+/// or an I/O error if it can't be read, unpacked, or (with a cache) written into it.
+pub fn extract_verified(
+    archive: &Path,
+    hash: &str,
+    algorithm: Option<HashAlgorithm>,
+    out_dir: &Path,
+    cache: Option<&ChunkCache>,
+) -> Result<Option<CacheStats>> {
+    hasher::verify(File::open(archive)?, hash, algorithm)?;
+
+    let Some(cache) = cache else {
+        let decoder = flate2::read::GzDecoder::new(File::open(archive)?);
+        let mut tar = tar::Archive::new(decoder);
+        tar.unpack(out_dir)?;
+        return Ok(None);
+    };
+
+    Ok(Some(extract_into_cache(archive, out_dir, cache)?))
+}
+
+/// Unpack `archive`'s already-verified contents into `out_dir` through `cache`, preserving
+/// directory structure, file permissions, and symlinks the same way [`tar::Archive::unpack`]
+/// would.
+fn extract_into_cache(archive: &Path, out_dir: &Path, cache: &ChunkCache) -> Result<CacheStats> {
+    let decoder = flate2::read::GzDecoder::new(File::open(archive)?);
+    let mut tar = tar::Archive::new(decoder);
+    let mut stats = CacheStats::default();
+
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        let relative_path = entry.path()?.into_owned();
+        let path = out_dir.join(&relative_path);
+        let entry_type = entry.header().entry_type();
+
+        if entry_type.is_dir() {
+            fs::create_dir_all(&path)?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        if entry_type.is_symlink() {
+            let target = entry
+                .link_name()?
+                .ok_or_else(|| std::io::Error::other("symlink entry missing a target"))?;
+            create_symlink(&target, &path)?;
+            continue;
+        }
+
+        let mut data = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut data)?;
+        let manifest = cache.store(&data, &mut stats)?;
+        fs::write(&path, cache.reassemble(&manifest)?)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(entry.header().mode()?))?;
+        }
+    }
+
+    Ok(stats)
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, path: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, path)
+}
+
+#[cfg(not(unix))]
+fn create_symlink(_target: &Path, _path: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::other(
+        "symlinks in archives aren't supported on this platform",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hasher::HashAlgorithm;
+    use std::io::Write;
+
+    /// Build a gzip'd tar fixture containing one regular (executable) file, one directory, and
+    /// (on unix) one symlink pointing at it, returning the archive bytes and its SHA-256.
+    fn build_archive() -> (Vec<u8>, String) {
+        let mut builder = tar::Builder::new(Vec::new());
+
+        let mut header = tar::Header::new_gnu();
+        header.set_size(11);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "bin/pg_ctl", b"executable\n".as_slice())
+            .unwrap();
+
+        #[cfg(unix)]
+        {
+            let mut link_header = tar::Header::new_gnu();
+            link_header.set_entry_type(tar::EntryType::Symlink);
+            link_header.set_size(0);
+            link_header.set_cksum();
+            builder
+                .append_link(&mut link_header, "bin/pg_ctl.link", "pg_ctl")
+                .unwrap();
+        }
+
+        let tar_bytes = builder.into_inner().unwrap();
+
+        let mut encoder =
+            flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&tar_bytes).unwrap();
+        let archive_bytes = encoder.finish().unwrap();
+
+        let hash = HashAlgorithm::Sha256
+            .digest(archive_bytes.as_slice())
+            .unwrap();
+
+        (archive_bytes, hash)
+    }
+
+    #[test]
+    fn test_extract_verified_without_cache_preserves_permissions() {
+        let (archive_bytes, hash) = build_archive();
+        let archive_dir = tempfile::tempdir().unwrap();
+        let archive_path = archive_dir.path().join("archive.tar.gz");
+        fs::write(&archive_path, &archive_bytes).unwrap();
+        let out_dir = tempfile::tempdir().unwrap();
+
+        let stats = extract_verified(&archive_path, &hash, None, out_dir.path(), None).unwrap();
+        assert!(stats.is_none());
+
+        let extracted = out_dir.path().join("bin/pg_ctl");
+        assert_eq!(fs::read(&extracted).unwrap(), b"executable\n");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&extracted).unwrap().permissions().mode() & 0o777, 0o755);
+            assert_eq!(
+                fs::read_link(out_dir.path().join("bin/pg_ctl.link")).unwrap(),
+                Path::new("pg_ctl")
+            );
+        }
+    }
+
+    #[test]
+    fn test_extract_verified_with_cache_preserves_permissions_and_dedupes() {
+        let (archive_bytes, hash) = build_archive();
+        let archive_dir = tempfile::tempdir().unwrap();
+        let archive_path = archive_dir.path().join("archive.tar.gz");
+        fs::write(&archive_path, &archive_bytes).unwrap();
+        let out_dir = tempfile::tempdir().unwrap();
+        let cache_dir = tempfile::tempdir().unwrap();
+        let cache = ChunkCache::open(cache_dir.path()).unwrap();
+
+        let stats = extract_verified(&archive_path, &hash, None, out_dir.path(), Some(&cache))
+            .unwrap()
+            .expect("cache was provided");
+        assert!(stats.total_bytes > 0);
+
+        let extracted = out_dir.path().join("bin/pg_ctl");
+        assert_eq!(fs::read(&extracted).unwrap(), b"executable\n");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&extracted).unwrap().permissions().mode() & 0o777, 0o755);
+            assert_eq!(
+                fs::read_link(out_dir.path().join("bin/pg_ctl.link")).unwrap(),
+                Path::new("pg_ctl")
+            );
+        }
+
+        // Extracting the same archive again through the same cache writes no new chunks.
+        let second_out_dir = tempfile::tempdir().unwrap();
+        let second = extract_verified(&archive_path, &hash, None, second_out_dir.path(), Some(&cache))
+            .unwrap()
+            .expect("cache was provided");
+        assert_eq!(second.total_bytes, stats.total_bytes);
+        assert_eq!(second.stored_bytes, 0);
+    }
+
+    #[test]
+    fn test_extract_with_settings_no_cache_dir_behaves_like_extract() {
+        let (archive_bytes, hash) = build_archive();
+        let archive_dir = tempfile::tempdir().unwrap();
+        let archive_path = archive_dir.path().join("archive.tar.gz");
+        fs::write(&archive_path, &archive_bytes).unwrap();
+        let out_dir = tempfile::tempdir().unwrap();
+
+        let stats = extract_with_settings(&archive_path, &hash, out_dir.path(), &Settings::new())
+            .unwrap();
+        assert!(stats.is_none());
+        assert_eq!(
+            fs::read(out_dir.path().join("bin/pg_ctl")).unwrap(),
+            b"executable\n"
+        );
+    }
+
+    #[test]
+    fn test_extract_with_settings_cache_dir_routes_through_cache() {
+        let (archive_bytes, hash) = build_archive();
+        let archive_dir = tempfile::tempdir().unwrap();
+        let archive_path = archive_dir.path().join("archive.tar.gz");
+        fs::write(&archive_path, &archive_bytes).unwrap();
+        let out_dir = tempfile::tempdir().unwrap();
+        let cache_dir = tempfile::tempdir().unwrap();
+        let settings = Settings::new().cache_dir(cache_dir.path());
+
+        let stats = extract_with_settings(&archive_path, &hash, out_dir.path(), &settings)
+            .unwrap()
+            .expect("cache_dir was set");
+        assert!(stats.total_bytes > 0);
+        assert_eq!(
+            fs::read(out_dir.path().join("bin/pg_ctl")).unwrap(),
+            b"executable\n"
+        );
+        assert!(fs::read_dir(cache_dir.path()).unwrap().next().is_some());
+    }
+}
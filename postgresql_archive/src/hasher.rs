@@ -0,0 +1,109 @@
+use crate::error::{Error, Result};
+use sha2::{Digest, Sha256, Sha512};
+use std::io::Read;
+
+/// Digest algorithm used to verify a downloaded release archive against its published hash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    /// Infer the algorithm from the length of a hex-encoded digest string, as published
+    /// alongside release archives.
+    ///
+    /// # Errors
+    /// Returns [`Error::UnsupportedHashLength`] if `hash` isn't a 64 or 128 hex-character digest.
+    pub fn from_hash_len(hash: &str) -> Result<Self> {
+        match hash.trim().len() {
+            64 => Ok(Self::Sha256),
+            128 => Ok(Self::Sha512),
+            len => Err(Error::UnsupportedHashLength(len)),
+        }
+    }
+
+    /// Compute this algorithm's digest over `reader`, reading it incrementally so large archives
+    /// don't need to be buffered fully in memory.
+    ///
+    /// # Errors
+    /// Returns an error if `reader` cannot be read.
+    pub fn digest(self, mut reader: impl Read) -> Result<String> {
+        let mut buffer = [0_u8; 64 * 1024];
+
+        macro_rules! hash_with {
+            ($hasher:expr) => {{
+                let mut hasher = $hasher;
+                loop {
+                    let bytes_read = reader.read(&mut buffer)?;
+                    if bytes_read == 0 {
+                        break;
+                    }
+                    hasher.update(&buffer[..bytes_read]);
+                }
+                hex::encode(hasher.finalize())
+            }};
+        }
+
+        let digest = match self {
+            Self::Sha256 => hash_with!(Sha256::new()),
+            Self::Sha512 => hash_with!(Sha512::new()),
+        };
+
+        Ok(digest)
+    }
+}
+
+/// Verify that `reader`'s content hashes to `expected`, selecting the digest algorithm with
+/// [`HashAlgorithm::from_hash_len`] unless `algorithm` is given explicitly.
+///
+/// # Errors
+/// Returns [`Error::HashMismatch`] if the computed digest doesn't match `expected`, or
+/// [`Error::UnsupportedHashLength`] if the algorithm can't be inferred.
+pub fn verify(reader: impl Read, expected: &str, algorithm: Option<HashAlgorithm>) -> Result<()> {
+    let algorithm = match algorithm {
+        Some(algorithm) => algorithm,
+        None => HashAlgorithm::from_hash_len(expected)?,
+    };
+    let actual = algorithm.digest(reader)?;
+
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(Error::HashMismatch {
+            expected: expected.trim().to_string(),
+            actual,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_hash_len() {
+        assert_eq!(
+            HashAlgorithm::Sha256,
+            HashAlgorithm::from_hash_len(&"a".repeat(64)).unwrap()
+        );
+        assert_eq!(
+            HashAlgorithm::Sha512,
+            HashAlgorithm::from_hash_len(&"a".repeat(128)).unwrap()
+        );
+        assert!(HashAlgorithm::from_hash_len("too-short").is_err());
+    }
+
+    #[test]
+    fn test_verify_success() {
+        let expected = HashAlgorithm::Sha256.digest(b"hello world".as_slice()).unwrap();
+        verify(b"hello world".as_slice(), &expected, None).unwrap();
+    }
+
+    #[test]
+    fn test_verify_mismatch() {
+        let expected = HashAlgorithm::Sha256.digest(b"hello world".as_slice()).unwrap();
+        let error = verify(b"goodbye world".as_slice(), &expected, None).unwrap_err();
+        assert!(matches!(error, Error::HashMismatch { .. }));
+    }
+}
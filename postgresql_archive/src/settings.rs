@@ -0,0 +1,112 @@
+use crate::encryption::EncryptionKey;
+use crate::error::{Error, Result};
+use std::path::{Path, PathBuf};
+
+/// Settings controlling how archives are cached and extracted.
+///
+/// With [`cache_dir`](Self::cache_dir) set, [`crate::extractor::extract_with_settings`] routes
+/// extraction through a deduplicating [`crate::cache::ChunkCache`] rooted there instead of
+/// unpacking the archive directly; [`encryption_key`](Self::encryption_key) /
+/// [`encryption_key_file`](Self::encryption_key_file) then further seal that cache at rest. Pass
+/// these settings to [`crate::cache::ChunkCache::open_with_settings`] when building a
+/// [`crate::cache::ChunkCache`] by hand instead; it resolves and applies the key itself.
+#[derive(Clone, Default)]
+pub struct Settings {
+    cache_dir: Option<PathBuf>,
+    encryption_key: Option<EncryptionKey>,
+    encryption_key_file: Option<PathBuf>,
+}
+
+impl Settings {
+    /// Create a new [`Settings`] with no caching or encryption configured.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Route extraction through a deduplicating [`crate::cache::ChunkCache`] rooted at `dir`
+    /// instead of unpacking archives directly. Shared across `PostgreSQL` versions/installs that
+    /// pass the same `dir`.
+    #[must_use]
+    pub fn cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// The configured cache directory, if any.
+    #[must_use]
+    pub fn get_cache_dir(&self) -> Option<&Path> {
+        self.cache_dir.as_deref()
+    }
+
+    /// Encrypt cached archive chunks at rest with `key`. Mutually exclusive with
+    /// [`encryption_key_file`](Self::encryption_key_file). Has no effect without
+    /// [`cache_dir`](Self::cache_dir).
+    #[must_use]
+    pub fn encryption_key(mut self, key: EncryptionKey) -> Self {
+        self.encryption_key = Some(key);
+        self
+    }
+
+    /// Encrypt cached archive chunks at rest with hex-encoded key material read from `path`.
+    /// Mutually exclusive with [`encryption_key`](Self::encryption_key). Has no effect without
+    /// [`cache_dir`](Self::cache_dir).
+    #[must_use]
+    pub fn encryption_key_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.encryption_key_file = Some(path.into());
+        self
+    }
+
+    /// Resolve the configured encryption key, if any.
+    ///
+    /// # Errors
+    /// Returns an error if both [`encryption_key`](Self::encryption_key) and
+    /// [`encryption_key_file`](Self::encryption_key_file) are set, or the key file can't be read
+    /// or doesn't contain valid key material.
+    pub fn resolve_encryption_key(&self) -> Result<Option<EncryptionKey>> {
+        match (&self.encryption_key, &self.encryption_key_file) {
+            (Some(_), Some(_)) => Err(Error::InvalidKeyMaterial),
+            (Some(key), None) => Ok(Some(key.clone())),
+            (None, Some(path)) => Ok(Some(EncryptionKey::from_file(path)?)),
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_dir_unset() {
+        assert!(Settings::new().get_cache_dir().is_none());
+    }
+
+    #[test]
+    fn test_cache_dir_set() {
+        let settings = Settings::new().cache_dir("/tmp/cache");
+        assert_eq!(settings.get_cache_dir(), Some(Path::new("/tmp/cache")));
+    }
+
+    #[test]
+    fn test_resolve_encryption_key_unset() {
+        assert!(Settings::new().resolve_encryption_key().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_resolve_encryption_key_inline() {
+        let settings = Settings::new().encryption_key(EncryptionKey::from_bytes([1_u8; 32]));
+        assert!(settings.resolve_encryption_key().unwrap().is_some());
+    }
+
+    #[test]
+    fn test_resolve_encryption_key_both_set_errors() {
+        let settings = Settings::new()
+            .encryption_key(EncryptionKey::from_bytes([1_u8; 32]))
+            .encryption_key_file("/nonexistent");
+        assert!(matches!(
+            settings.resolve_encryption_key(),
+            Err(Error::InvalidKeyMaterial)
+        ));
+    }
+}
@@ -0,0 +1,89 @@
+use metrics::{counter, histogram};
+use std::process::Output;
+use std::time::{Duration, Instant};
+
+/// Number of times a wrapped command has been invoked, labeled by `program` and `outcome`.
+const INVOCATIONS: &str = "postgresql_commands.invocations";
+
+/// Wall-clock command duration in seconds, labeled by `program` and `outcome`.
+const DURATION_SECONDS: &str = "postgresql_commands.duration_seconds";
+
+/// Bytes extracted/transferred by archive-oriented commands, labeled by `program`.
+const BYTES_EXTRACTED: &str = "postgresql_commands.bytes_extracted";
+
+/// Run `run`, recording its invocation count, exit-code outcome, and wall-clock duration as
+/// metrics labeled with `program`, via the [`metrics`] facade.
+///
+/// Emitting these is a no-op until the embedding application installs a recorder (e.g.
+/// `metrics_exporter_prometheus::PrometheusBuilder`), so this is zero-cost for callers who don't
+/// wire one up.
+pub fn instrument(
+    program: &'static str,
+    run: impl FnOnce() -> std::io::Result<Output>,
+) -> std::io::Result<Output> {
+    let start = Instant::now();
+    let result = run();
+    record_invocation(program, outcome_label(&result), start.elapsed());
+    result
+}
+
+/// Classify a completed run's result into the `outcome` label recorded alongside it: `"success"`
+/// for a zero exit status, `"failure"` for a non-zero one, `"error"` when the command couldn't
+/// even be run/waited on.
+fn outcome_label(result: &std::io::Result<Output>) -> &'static str {
+    match result {
+        Ok(output) if output.status.success() => "success",
+        Ok(_) => "failure",
+        Err(_) => "error",
+    }
+}
+
+/// Record one invocation of `program` directly, for run styles that don't produce a
+/// [`Output`] (e.g. a long-lived streamed child process). Prefer [`instrument`] when a plain
+/// `Command::output()` call fits the call site.
+pub fn record_invocation(program: &'static str, outcome: &'static str, duration: Duration) {
+    counter!(INVOCATIONS, "program" => program, "outcome" => outcome).increment(1);
+    histogram!(DURATION_SECONDS, "program" => program, "outcome" => outcome)
+        .record(duration.as_secs_f64());
+}
+
+/// Record `bytes` extracted/transferred by an archive-oriented command (e.g. `pg_basebackup`),
+/// labeled by `program`.
+pub fn record_bytes_extracted(program: &'static str, bytes: u64) {
+    histogram!(BYTES_EXTRACTED, "program" => program).record(bytes as f64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    fn output_with_exit_code(code: i32) -> Output {
+        use std::os::unix::process::ExitStatusExt;
+        Output {
+            status: std::process::ExitStatus::from_raw(code << 8),
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_instrument_success() {
+        let result = instrument("pg_ctl", || Ok(output_with_exit_code(0)));
+        assert_eq!(outcome_label(&result), "success");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_instrument_failure() {
+        let result = instrument("pg_ctl", || Ok(output_with_exit_code(1)));
+        assert_eq!(outcome_label(&result), "failure");
+    }
+
+    #[test]
+    fn test_instrument_error() {
+        let result = instrument("pg_ctl", || Err(std::io::Error::other("failed to spawn")));
+        assert_eq!(outcome_label(&result), "error");
+    }
+}
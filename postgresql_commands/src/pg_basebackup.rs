@@ -0,0 +1,539 @@
+use crate::traits::CommandBuilder;
+use crate::Settings;
+use std::convert::AsRef;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+/// `pg_basebackup` takes a base backup of a running `PostgreSQL` server.
+#[derive(Clone, Debug, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct PgBaseBackupBuilder {
+    program_dir: Option<PathBuf>,
+    envs: Vec<(OsString, OsString)>,
+    pgdata: Option<OsString>,
+    format: Option<OsString>,
+    wal_method: Option<OsString>,
+    checkpoint: Option<OsString>,
+    compress: Option<OsString>,
+    rate: Option<OsString>,
+    slot: Option<OsString>,
+    host: Option<OsString>,
+    port: Option<OsString>,
+    username: Option<OsString>,
+    password: Option<OsString>,
+    password_file: Option<PathBuf>,
+    progress: bool,
+    max_rate: Option<OsString>,
+    help: bool,
+    version: bool,
+}
+
+impl PgBaseBackupBuilder {
+    /// Create a new [`PgBaseBackupBuilder`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new [`PgBaseBackupBuilder`] from [Settings]
+    pub fn from(settings: &dyn Settings) -> Self {
+        Self::new().program_dir(settings.get_binary_dir())
+    }
+
+    /// Location of the program binary
+    #[must_use]
+    pub fn program_dir<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.program_dir = Some(path.into());
+        self
+    }
+
+    /// receive base backup into directory
+    #[must_use]
+    pub fn pgdata<S: AsRef<OsStr>>(mut self, pgdata: S) -> Self {
+        self.pgdata = Some(pgdata.as_ref().to_os_string());
+        self
+    }
+
+    /// output format (plain (default), tar)
+    #[must_use]
+    pub fn format<S: AsRef<OsStr>>(mut self, format: S) -> Self {
+        self.format = Some(format.as_ref().to_os_string());
+        self
+    }
+
+    /// WAL-method (none, fetch, stream)
+    #[must_use]
+    pub fn wal_method<S: AsRef<OsStr>>(mut self, wal_method: S) -> Self {
+        self.wal_method = Some(wal_method.as_ref().to_os_string());
+        self
+    }
+
+    /// set fast or spread checkpointing
+    #[must_use]
+    pub fn checkpoint<S: AsRef<OsStr>>(mut self, checkpoint: S) -> Self {
+        self.checkpoint = Some(checkpoint.as_ref().to_os_string());
+        self
+    }
+
+    /// compress tar output
+    #[must_use]
+    pub fn compress<S: AsRef<OsStr>>(mut self, compress: S) -> Self {
+        self.compress = Some(compress.as_ref().to_os_string());
+        self
+    }
+
+    /// maximum transfer rate to transfer data directory
+    #[must_use]
+    pub fn rate<S: AsRef<OsStr>>(mut self, rate: S) -> Self {
+        self.rate = Some(rate.as_ref().to_os_string());
+        self
+    }
+
+    /// replication slot to use
+    #[must_use]
+    pub fn slot<S: AsRef<OsStr>>(mut self, slot: S) -> Self {
+        self.slot = Some(slot.as_ref().to_os_string());
+        self
+    }
+
+    /// database server host or socket directory
+    #[must_use]
+    pub fn host<S: AsRef<OsStr>>(mut self, host: S) -> Self {
+        self.host = Some(host.as_ref().to_os_string());
+        self
+    }
+
+    /// database server port number
+    #[must_use]
+    pub fn port<S: AsRef<OsStr>>(mut self, port: S) -> Self {
+        self.port = Some(port.as_ref().to_os_string());
+        self
+    }
+
+    /// connect as specified database user
+    #[must_use]
+    pub fn username<S: AsRef<OsStr>>(mut self, username: S) -> Self {
+        self.username = Some(username.as_ref().to_os_string());
+        self
+    }
+
+    /// password to authenticate with, passed via the `PGPASSWORD` environment variable rather
+    /// than the command line. Read lazily by [`get_envs`](CommandBuilder::get_envs) at `build()`
+    /// time via [`resolve_password`](Self::resolve_password). Mutually exclusive with
+    /// [`password_file`](Self::password_file).
+    #[must_use]
+    pub fn password<S: AsRef<OsStr>>(mut self, password: S) -> Self {
+        self.password = Some(password.as_ref().to_os_string());
+        self
+    }
+
+    /// read the password to authenticate with from `path` at run time, trimmed of its trailing
+    /// newline, instead of holding it inline. Mutually exclusive with
+    /// [`password`](Self::password).
+    #[must_use]
+    pub fn password_file<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.password_file = Some(path.into());
+        self
+    }
+
+    /// Resolve [`password`](Self::password) / [`password_file`](Self::password_file) into a
+    /// `PGPASSWORD` value, if either was set.
+    ///
+    /// [`get_envs`](CommandBuilder::get_envs) calls this for every build and panics if it
+    /// errors, since `get_envs` has no way to report a `Result`; call this directly first if a
+    /// misconfigured password should surface as an `Err` instead.
+    ///
+    /// # Errors
+    /// Returns an error if both were set, or if the password file cannot be read.
+    pub fn resolve_password(&self) -> std::io::Result<Option<OsString>> {
+        crate::secret::resolve("password", &self.password, &self.password_file)
+    }
+
+    /// show progress information
+    #[must_use]
+    pub fn progress(mut self) -> Self {
+        self.progress = true;
+        self
+    }
+
+    /// maximum transfer rate to transfer data directory (alias for [`rate`](Self::rate))
+    #[must_use]
+    pub fn max_rate<S: AsRef<OsStr>>(mut self, max_rate: S) -> Self {
+        self.max_rate = Some(max_rate.as_ref().to_os_string());
+        self
+    }
+
+    /// output version information, then exit
+    #[must_use]
+    pub fn version(mut self) -> Self {
+        self.version = true;
+        self
+    }
+
+    /// show help, then exit
+    #[must_use]
+    pub fn help(mut self) -> Self {
+        self.help = true;
+        self
+    }
+}
+
+impl CommandBuilder for PgBaseBackupBuilder {
+    /// Get the program name
+    fn get_program(&self) -> &'static OsStr {
+        "pg_basebackup".as_ref()
+    }
+
+    /// Location of the program binary
+    fn get_program_dir(&self) -> &Option<PathBuf> {
+        &self.program_dir
+    }
+
+    /// Get the arguments for the command
+    fn get_args(&self) -> Vec<OsString> {
+        let mut args: Vec<OsString> = Vec::new();
+
+        if let Some(pgdata) = &self.pgdata {
+            args.push("--pgdata".into());
+            args.push(pgdata.into());
+        }
+
+        if let Some(format) = &self.format {
+            args.push("--format".into());
+            args.push(format.into());
+        }
+
+        if let Some(wal_method) = &self.wal_method {
+            args.push("--wal-method".into());
+            args.push(wal_method.into());
+        }
+
+        if let Some(checkpoint) = &self.checkpoint {
+            args.push("--checkpoint".into());
+            args.push(checkpoint.into());
+        }
+
+        if let Some(compress) = &self.compress {
+            args.push("--compress".into());
+            args.push(compress.into());
+        }
+
+        if let Some(rate) = &self.rate {
+            args.push("--rate".into());
+            args.push(rate.into());
+        }
+
+        if let Some(slot) = &self.slot {
+            args.push("--slot".into());
+            args.push(slot.into());
+        }
+
+        if let Some(host) = &self.host {
+            args.push("--host".into());
+            args.push(host.into());
+        }
+
+        if let Some(port) = &self.port {
+            args.push("--port".into());
+            args.push(port.into());
+        }
+
+        if let Some(username) = &self.username {
+            args.push("--username".into());
+            args.push(username.into());
+        }
+
+        if self.progress {
+            args.push("--progress".into());
+        }
+
+        if let Some(max_rate) = &self.max_rate {
+            args.push("--max-rate".into());
+            args.push(max_rate.into());
+        }
+
+        if self.version {
+            args.push("--version".into());
+        }
+
+        if self.help {
+            args.push("--help".into());
+        }
+
+        args
+    }
+
+    /// Get the environment variables for the command
+    fn get_envs(&self) -> Vec<(OsString, OsString)> {
+        let mut envs = self.envs.clone();
+        // get_envs() can't return a Result, so a misconfigured password (both password and
+        // password_file set, or an unreadable password_file) surfaces as a panic here rather
+        // than silently running unauthenticated; call resolve_password() directly for a
+        // Result-based check before build() if that's not acceptable.
+        if let Some(password) = self
+            .resolve_password()
+            .expect("failed to resolve password/password_file")
+        {
+            envs.push((OsString::from("PGPASSWORD"), password));
+        }
+        envs
+    }
+
+    /// Set an environment variable for the command
+    fn env<S: AsRef<OsStr>>(mut self, key: S, value: S) -> Self {
+        self.envs
+            .push((key.as_ref().to_os_string(), value.as_ref().to_os_string()));
+        self
+    }
+}
+
+/// Clone a running server's data directory into `pgdata` by streaming a tar-format base backup
+/// over `pg_basebackup` and unpacking it as it arrives. If `builder` has
+/// [`compress`](PgBaseBackupBuilder::compress) set, the stream is gunzipped transparently first
+/// (`--format=tar` with `--compress` produces a gzipped tar on the wire).
+///
+/// `pgdata` is created with `0700` permissions if it doesn't already exist, matching the
+/// permissions `initdb` itself requires. `on_progress` is called after each archive member is
+/// written, with the cumulative number of bytes extracted so far.
+///
+/// # Errors
+///
+/// Returns an error if `pgdata` cannot be created, `pg_basebackup` fails to start or exits with
+/// a non-zero status, or the streamed archive cannot be unpacked.
+pub fn bootstrap_from_base_backup<F>(
+    builder: PgBaseBackupBuilder,
+    pgdata: impl AsRef<Path>,
+    mut on_progress: F,
+) -> std::io::Result<u64>
+where
+    F: FnMut(u64),
+{
+    let pgdata = pgdata.as_ref();
+    fs::create_dir_all(pgdata)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(pgdata, fs::Permissions::from_mode(0o700))?;
+    }
+
+    let start = std::time::Instant::now();
+    // Validate eagerly so a misconfigured password surfaces as an `Err` here rather than as a
+    // panic out of `build()`'s `get_envs()`, which applies it automatically.
+    builder.resolve_password()?;
+    let compressed = builder.compress.is_some();
+    let builder = builder.pgdata("-").format("tar");
+    let mut child = builder.build().stdout(Stdio::piped()).spawn()?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| std::io::Error::other("failed to capture pg_basebackup stdout"))?;
+
+    let reader = BufReader::new(stdout);
+    let bytes_extracted = if compressed {
+        unpack_tar(
+            flate2::read::GzDecoder::new(reader),
+            pgdata,
+            &mut on_progress,
+        )?
+    } else {
+        unpack_tar(reader, pgdata, &mut on_progress)?
+    };
+
+    let status = child.wait()?;
+    let outcome = if status.success() { "success" } else { "failure" };
+    crate::metrics::record_invocation("pg_basebackup", outcome, start.elapsed());
+    crate::metrics::record_bytes_extracted("pg_basebackup", bytes_extracted);
+
+    if !status.success() {
+        return Err(std::io::Error::other(format!(
+            "pg_basebackup exited with {status}"
+        )));
+    }
+
+    Ok(bytes_extracted)
+}
+
+/// Unpack a tar stream (already gunzipped by the caller if needed) into `pgdata`, calling
+/// `on_progress` after each member with the cumulative bytes extracted so far.
+fn unpack_tar(
+    reader: impl Read,
+    pgdata: &Path,
+    on_progress: &mut dyn FnMut(u64),
+) -> std::io::Result<u64> {
+    let mut archive = tar::Archive::new(reader);
+    let mut bytes_extracted = 0u64;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        bytes_extracted += entry.size();
+        entry.unpack_in(pgdata)?;
+        on_progress(bytes_extracted);
+    }
+    Ok(bytes_extracted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::CommandToString;
+    use crate::TestSettings;
+    use test_log::test;
+
+    #[test]
+    fn test_builder_new() {
+        let command = PgBaseBackupBuilder::new().program_dir(".").build();
+        assert_eq!(
+            PathBuf::from(".").join("pg_basebackup"),
+            PathBuf::from(command.to_command_string().replace('"', ""))
+        );
+    }
+
+    #[test]
+    fn test_builder_from() {
+        let command = PgBaseBackupBuilder::from(&TestSettings).build();
+        assert_eq!(r#""./pg_basebackup""#, command.to_command_string());
+    }
+
+    #[test]
+    fn test_builder() {
+        let command = PgBaseBackupBuilder::new()
+            .env("PGDATABASE", "database")
+            .pgdata("pgdata")
+            .format("tar")
+            .wal_method("stream")
+            .checkpoint("fast")
+            .compress("9")
+            .rate("1M")
+            .slot("slot")
+            .host("localhost")
+            .port("5432")
+            .username("postgres")
+            .progress()
+            .max_rate("1M")
+            .version()
+            .help()
+            .build();
+
+        assert_eq!(
+            r#"PGDATABASE="database" "pg_basebackup" "--pgdata" "pgdata" "--format" "tar" "--wal-method" "stream" "--checkpoint" "fast" "--compress" "9" "--rate" "1M" "--slot" "slot" "--host" "localhost" "--port" "5432" "--username" "postgres" "--progress" "--max-rate" "1M" "--version" "--help""#,
+            command.to_command_string()
+        );
+    }
+
+    #[test]
+    fn test_resolve_password_inline() {
+        let password = PgBaseBackupBuilder::new()
+            .password("hunter2")
+            .resolve_password()
+            .unwrap();
+        assert_eq!(password, Some(OsString::from("hunter2")));
+    }
+
+    #[test]
+    fn test_resolve_password_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(file.path(), "hunter2\n").unwrap();
+
+        let password = PgBaseBackupBuilder::new()
+            .password_file(file.path())
+            .resolve_password()
+            .unwrap();
+        assert_eq!(password, Some(OsString::from("hunter2")));
+    }
+
+    #[test]
+    fn test_resolve_password_both_set_errors() {
+        let error = PgBaseBackupBuilder::new()
+            .password("hunter2")
+            .password_file("/nonexistent")
+            .resolve_password()
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_password_alone_sets_pgpassword() {
+        let command = PgBaseBackupBuilder::new().password("hunter2").build();
+        assert_eq!(
+            r#"PGPASSWORD="hunter2" "pg_basebackup""#,
+            command.to_command_string()
+        );
+    }
+
+    #[test]
+    fn test_password_file_alone_sets_pgpassword() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(file.path(), "hunter2\n").unwrap();
+
+        let command = PgBaseBackupBuilder::new()
+            .password_file(file.path())
+            .build();
+        assert_eq!(
+            r#"PGPASSWORD="hunter2" "pg_basebackup""#,
+            command.to_command_string()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to resolve password/password_file")]
+    fn test_build_panics_when_password_and_password_file_both_set() {
+        PgBaseBackupBuilder::new()
+            .password("hunter2")
+            .password_file("/nonexistent")
+            .build();
+    }
+
+    /// Build an uncompressed tar fixture containing a single file, returning its bytes.
+    fn build_tar() -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(5);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "base.txt", b"hello".as_slice())
+            .unwrap();
+        builder.into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_unpack_tar_uncompressed() {
+        let tar_bytes = build_tar();
+        let pgdata = tempfile::tempdir().unwrap();
+        let mut progress = Vec::new();
+
+        let bytes_extracted = unpack_tar(tar_bytes.as_slice(), pgdata.path(), &mut |n| {
+            progress.push(n)
+        })
+        .unwrap();
+
+        assert_eq!(bytes_extracted, 5);
+        assert_eq!(fs::read(pgdata.path().join("base.txt")).unwrap(), b"hello");
+        assert_eq!(progress, vec![5]);
+    }
+
+    #[test]
+    fn test_unpack_tar_gzip_compressed() {
+        use std::io::Write;
+
+        let tar_bytes = build_tar();
+        let mut encoder =
+            flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&tar_bytes).unwrap();
+        let gz_bytes = encoder.finish().unwrap();
+
+        let pgdata = tempfile::tempdir().unwrap();
+        let mut progress = Vec::new();
+        let bytes_extracted = unpack_tar(
+            flate2::read::GzDecoder::new(gz_bytes.as_slice()),
+            pgdata.path(),
+            &mut |n| progress.push(n),
+        )
+        .unwrap();
+
+        assert_eq!(bytes_extracted, 5);
+        assert_eq!(fs::read(pgdata.path().join("base.txt")).unwrap(), b"hello");
+        assert_eq!(progress, vec![5]);
+    }
+}
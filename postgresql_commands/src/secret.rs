@@ -0,0 +1,75 @@
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Resolve a credential-bearing builder option that may be set inline, sourced from a file, or
+/// left unset.
+///
+/// Mirrors the `password` / `password_file` pattern (itself mirroring `PGPASSFILE`): a file path
+/// is read lazily, here at resolution time, and trimmed of its trailing newline, so secrets don't
+/// need to live in source or shell history. `field` names the option in the returned error.
+///
+/// Currently only [`PgBaseBackupBuilder`](crate::pg_basebackup::PgBaseBackupBuilder) wires this
+/// in; no other credential-bearing builder in this crate uses it yet, and there is no
+/// `Settings`-level `PGPASSFILE`-style default for callers who don't set one explicitly per
+/// builder.
+///
+/// # Errors
+/// Returns an error if both `value` and `file` are set, or if `file` is set but cannot be read.
+pub fn resolve(
+    field: &str,
+    value: &Option<OsString>,
+    file: &Option<PathBuf>,
+) -> io::Result<Option<OsString>> {
+    match (value, file) {
+        (Some(_), Some(_)) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("both `{field}` and `{field}_file` were set; only one may be used"),
+        )),
+        (Some(value), None) => Ok(Some(value.clone())),
+        (None, Some(path)) => {
+            let contents = fs::read_to_string(path)?;
+            Ok(Some(OsString::from(contents.trim_end_matches(['\n', '\r']))))
+        }
+        (None, None) => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_unset() {
+        assert_eq!(resolve("password", &None, &None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_resolve_inline() {
+        let value = Some(OsString::from("hunter2"));
+        assert_eq!(
+            resolve("password", &value, &None).unwrap(),
+            Some(OsString::from("hunter2"))
+        );
+    }
+
+    #[test]
+    fn test_resolve_file_trims_trailing_newline() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(file.path(), "hunter2\n").unwrap();
+        let path = Some(file.path().to_path_buf());
+        assert_eq!(
+            resolve("password", &None, &path).unwrap(),
+            Some(OsString::from("hunter2"))
+        );
+    }
+
+    #[test]
+    fn test_resolve_both_set_errors() {
+        let value = Some(OsString::from("hunter2"));
+        let file = Some(PathBuf::from("/nonexistent"));
+        let error = resolve("password", &value, &file).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::BTreeMap;
+
+/// A `(tablespace, database, relnode)` triple identifying a relation file, as printed by
+/// `pg_waldump` in the form `T/D/R`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RelFileNode {
+    pub tablespace: u32,
+    pub database: u32,
+    pub relnode: u32,
+}
+
+/// A single block reference attached to a [`WalRecord`], as printed after `blkref #N:` or, with
+/// `--bkp-details`, on the following `backup blk N:` continuation line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockRef {
+    pub rel: RelFileNode,
+    pub fork: String,
+    pub blkno: u32,
+    /// `true` when the block reference carries a full-page image (`FPW`).
+    pub fpw: bool,
+}
+
+/// A single parsed `pg_waldump` record line, plus any block references that follow it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WalRecord {
+    pub lsn: String,
+    pub prev_lsn: String,
+    pub xid: u64,
+    pub rmgr: String,
+    pub total_len: u32,
+    pub info: String,
+    pub description: String,
+    pub block_refs: Vec<BlockRef>,
+}
+
+/// Per-resource-manager totals, as printed by `pg_waldump --stats`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RmgrStats {
+    pub count: u64,
+    pub record_len: u64,
+    pub fpi_len: u64,
+    pub combined_len: u64,
+}
+
+/// The parsed result of `pg_waldump --stats`, keyed by resource manager name.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WalStats {
+    pub by_rmgr: BTreeMap<String, RmgrStats>,
+}
+
+/// One item produced while following a live WAL stream with `--follow`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FollowEvent {
+    /// A record parsed from its `rmgr:` line, including any `blkref #N:` block references on
+    /// that line; `backup blk N:` continuation lines are not attached.
+    Record(WalRecord),
+    /// `pg_waldump` reached the end of the available WAL and is waiting for more; not an error.
+    WaitingForWal,
+}
+
+/// Parse the textual output of a non-`--stats` `pg_waldump` invocation into records.
+///
+/// Recognizes the standard one-line-per-record format, e.g.
+/// `rmgr: Heap        len (rec/tot):     54/    54, tx:        719, lsn: 0/01862E28, prev 0/01862DF0, desc: INSERT ...`
+/// as well as `blkref #N:` suffixes and `--bkp-details` `backup blk N:` continuation lines.
+#[must_use]
+pub fn parse_records(output: &str) -> Vec<WalRecord> {
+    let mut records = Vec::new();
+
+    for line in output.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("rmgr:") {
+            if let Some(record) = parse_record_line(trimmed) {
+                records.push(record);
+            }
+        } else if trimmed.starts_with("backup blk") {
+            if let (Some(record), Some(block_ref)) =
+                (records.last_mut(), parse_backup_blk_line(trimmed))
+            {
+                record.block_refs.push(block_ref);
+            }
+        }
+    }
+
+    records
+}
+
+/// Parse the textual output of a `pg_waldump --stats` invocation into per-rmgr totals.
+#[must_use]
+pub fn parse_stats(output: &str) -> WalStats {
+    let mut stats = WalStats::default();
+
+    for line in output.lines() {
+        // Data rows look like `Heap    2 ( 50.00)    108 ( 45.00)    0 (  0.00)    108 ( 30.00)`;
+        // drop the parenthesized percentages so only the rmgr name and its four totals remain.
+        let fields: Vec<&str> = line
+            .split_whitespace()
+            .filter(|field| !field.starts_with('(') && !field.ends_with(')') && *field != "-")
+            .collect();
+        let [rmgr, count, record_len, fpi_len, combined_len, ..] = fields.as_slice() else {
+            continue;
+        };
+        let (Ok(count), Ok(record_len), Ok(fpi_len), Ok(combined_len)) = (
+            count.parse(),
+            record_len.parse(),
+            fpi_len.parse(),
+            combined_len.parse(),
+        ) else {
+            continue;
+        };
+        stats.by_rmgr.insert(
+            (*rmgr).to_string(),
+            RmgrStats {
+                count,
+                record_len,
+                fpi_len,
+                combined_len,
+            },
+        );
+    }
+
+    stats
+}
+
+fn parse_record_line(line: &str) -> Option<WalRecord> {
+    let rmgr = field_after(line, "rmgr:")?.split_whitespace().next()?.to_string();
+    // "len (rec/tot):     54/    54," - the total is the second number, itself followed by a
+    // comma rather than whitespace.
+    let total_len = field_after(line, "tot):")?
+        .split('/')
+        .nth(1)?
+        .split_whitespace()
+        .next()?
+        .trim_end_matches(',')
+        .parse()
+        .ok()?;
+    // "tx:        719," - also comma-terminated, not whitespace-terminated.
+    let xid = field_after(line, "tx:")?
+        .split_whitespace()
+        .next()?
+        .trim_end_matches(',')
+        .parse()
+        .ok()?;
+    let lsn = field_after(line, "lsn:")?
+        .split(',')
+        .next()?
+        .trim()
+        .to_string();
+    let prev_lsn = field_after(line, "prev ")?
+        .split(',')
+        .next()?
+        .trim()
+        .to_string();
+    let desc_and_refs = field_after(line, "desc:")?.trim();
+    let (description, block_refs) = match desc_and_refs.split_once("blkref #") {
+        Some((desc, refs)) => (desc.trim().to_string(), parse_blkrefs(refs)),
+        None => (desc_and_refs.to_string(), Vec::new()),
+    };
+    // `info` isn't printed as its own field by pg_waldump text output; the flags embedded in
+    // `description` (e.g. "flags 0x08") are the closest analogue, so surface the raw tail here.
+    let info = description
+        .split_once("flags ")
+        .map_or_else(String::new, |(_, flags)| flags.to_string());
+
+    Some(WalRecord {
+        lsn,
+        prev_lsn,
+        xid,
+        rmgr,
+        total_len,
+        info,
+        description,
+        block_refs,
+    })
+}
+
+fn parse_blkrefs(refs: &str) -> Vec<BlockRef> {
+    // `refs` is everything after the first "blkref #" was already stripped by the caller, so its
+    // first element here is block 0's own fragment (up to the next "blkref #", if any) -- using
+    // `refs` itself unsplit as that first fragment would let it see every later block's text too,
+    // e.g. a later block's "FPW" would wrongly mark block 0 as having a full-page image.
+    let mut block_refs = Vec::new();
+    for chunk in refs.split("blkref #") {
+        if let Some(block_ref) = parse_rel_fork_blk(chunk) {
+            block_refs.push(block_ref);
+        }
+    }
+    block_refs
+}
+
+fn parse_backup_blk_line(line: &str) -> Option<BlockRef> {
+    let rest = field_after(line, "backup blk")?;
+    let rest = rest.split_once(':').map_or(rest, |(_, r)| r);
+    parse_rel_fork_blk(rest)
+}
+
+/// Parse the common `rel T/D/R fork FORK blk N (FPW)` fragment shared by `blkref` and
+/// `backup blk` lines.
+fn parse_rel_fork_blk(fragment: &str) -> Option<BlockRef> {
+    let rel_str = field_after(fragment, "rel ")?.split_whitespace().next()?;
+    let mut parts = rel_str.split('/');
+    let rel = RelFileNode {
+        tablespace: parts.next()?.parse().ok()?,
+        database: parts.next()?.parse().ok()?,
+        relnode: parts.next()?.parse().ok()?,
+    };
+    let fork = field_after(fragment, "fork ")?
+        .split_whitespace()
+        .next()?
+        .to_string();
+    let blkno = field_after(fragment, "blk ")?
+        .split_whitespace()
+        .next()?
+        .trim_end_matches(',')
+        .parse()
+        .ok()?;
+    let fpw = fragment.contains("FPW");
+
+    Some(BlockRef {
+        rel,
+        fork,
+        blkno,
+        fpw,
+    })
+}
+
+/// Return the remainder of `line` after the first occurrence of `marker`.
+fn field_after<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    line.split_once(marker).map(|(_, rest)| rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_records_basic() {
+        let output = "rmgr: Heap        len (rec/tot):     54/    54, tx:        719, lsn: 0/01862E28, prev 0/01862DF0, desc: INSERT off 18 flags 0x08, blkref #0: rel 1663/13757/16384 fork main blk 1\n";
+        let records = parse_records(output);
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.rmgr, "Heap");
+        assert_eq!(record.total_len, 54);
+        assert_eq!(record.xid, 719);
+        assert_eq!(record.lsn, "0/01862E28");
+        assert_eq!(record.prev_lsn, "0/01862DF0");
+        assert_eq!(record.block_refs.len(), 1);
+        let block_ref = &record.block_refs[0];
+        assert_eq!(
+            block_ref.rel,
+            RelFileNode {
+                tablespace: 1663,
+                database: 13757,
+                relnode: 16384,
+            }
+        );
+        assert_eq!(block_ref.fork, "main");
+        assert_eq!(block_ref.blkno, 1);
+        assert!(!block_ref.fpw);
+    }
+
+    #[test]
+    fn test_parse_records_backup_blk_continuation() {
+        let output = "rmgr: Heap        len (rec/tot):     54/    54, tx:        719, lsn: 0/01862E28, prev 0/01862DF0, desc: INSERT off 18 flags 0x08\n        backup blk 0: rel 1663/13757/16384 fork main blk 1 (FPW)\n";
+        let records = parse_records(output);
+        assert_eq!(records.len(), 1);
+        let block_ref = &records[0].block_refs[0];
+        assert_eq!(block_ref.blkno, 1);
+        assert!(block_ref.fpw);
+    }
+
+    #[test]
+    fn test_parse_records_multiple_blkrefs_fpw_is_per_block() {
+        let output = "rmgr: Heap        len (rec/tot):     54/    54, tx:        719, lsn: 0/01862E28, prev 0/01862DF0, desc: INSERT off 18 flags 0x08, blkref #0: rel 1663/13757/16384 fork main blk 1, blkref #1: rel 1663/13757/16385 fork main blk 2 FPW\n";
+        let records = parse_records(output);
+        assert_eq!(records.len(), 1);
+        let block_refs = &records[0].block_refs;
+        assert_eq!(block_refs.len(), 2);
+        assert_eq!(block_refs[0].blkno, 1);
+        assert!(!block_refs[0].fpw, "block 0 has no FPW of its own");
+        assert_eq!(block_refs[1].blkno, 2);
+        assert!(block_refs[1].fpw);
+    }
+
+    #[test]
+    fn test_parse_stats() {
+        let output = "Type                           N      (%)          Record size      (%)             FPI size      (%)        Combined size      (%)\n--------                      -      ---          -----------      ---             --------      ---        -------------      ---\nHeap                           2 ( 50.00)                  108 ( 45.00)                    0 (  0.00)                  108 ( 30.00)\n";
+        let stats = parse_stats(output);
+        let heap = stats.by_rmgr.get("Heap").expect("Heap entry");
+        assert_eq!(heap.count, 2);
+        assert_eq!(heap.record_len, 108);
+    }
+}